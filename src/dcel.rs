@@ -1,3 +1,6 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+
 use nannou::prelude::{Point2, Vec2Angle};
 use slotmap::{
     basic::{Values, ValuesMut},
@@ -9,6 +12,14 @@ new_key_type! {pub struct HalfEdgeId;}
 new_key_type! {pub struct EdgeId;}
 new_key_type! {pub struct FaceId;}
 
+/// Errors returned by the DCEL's removal operations, so callers can detect an
+/// already-freed key instead of hitting a panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DcelError {
+    UnknownEdge(EdgeId),
+    UnknownVertex(VertexId),
+}
+
 pub struct Vertex<Data> {
     id: VertexId,
 
@@ -46,6 +57,9 @@ pub struct HalfEdge {
 
     pub next: HalfEdgeId, // TODO: make private
     prev: HalfEdgeId,
+
+    face: FaceId,
+    edge: EdgeId,
 }
 
 impl HalfEdge {
@@ -57,6 +71,8 @@ impl HalfEdge {
             twin: id,
             next: id,
             prev: id,
+            face: FaceId::default(),
+            edge: EdgeId::default(),
         }
     }
 
@@ -71,6 +87,17 @@ impl HalfEdge {
     pub fn target(&self) -> VertexId {
         self.target
     }
+
+    /// The face this half-edge bounds. Only meaningful after [`GeometricGraph::rebuild_faces`]
+    /// has been called since the last change to the DCEL's connectivity.
+    pub fn face(&self) -> FaceId {
+        self.face
+    }
+
+    /// The id of the `Edge` this half-edge and its twin together form.
+    pub fn edge(&self) -> EdgeId {
+        self.edge
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -122,7 +149,33 @@ impl Edge {
     }
 }
 
-struct Face {}
+pub struct Face {
+    id: FaceId,
+
+    /// One half-edge on this face's boundary; the rest are reached by following `next`.
+    boundary: HalfEdgeId,
+    /// Signed shoelace area of the boundary loop; negative for the unbounded outer face.
+    area: f32,
+    is_outer: bool,
+}
+
+impl Face {
+    pub fn id(&self) -> FaceId {
+        self.id
+    }
+
+    pub fn boundary(&self) -> HalfEdgeId {
+        self.boundary
+    }
+
+    pub fn area(&self) -> f32 {
+        self.area
+    }
+
+    pub fn is_outer(&self) -> bool {
+        self.is_outer
+    }
+}
 
 pub struct GeometricGraph<VertexData> {
     vertices: SlotMap<VertexId, Vertex<VertexData>>,
@@ -159,6 +212,9 @@ impl<VertexData> GeometricGraph<VertexData> {
             Edge::new(full_edge_id, edge_id, twin_id, origin, target)
         });
 
+        self.half_edge_mut(edge_id).edge = full_edge_id;
+        self.half_edge_mut(twin_id).edge = full_edge_id;
+
         // Set the edges as twins of each other
         self.half_edge_mut(edge_id).twin = twin_id;
         self.half_edge_mut(twin_id).twin = edge_id;
@@ -261,11 +317,109 @@ impl<VertexData> GeometricGraph<VertexData> {
         self.half_edge_mut(edge_id).prev = prev_id;
         self.half_edge_mut(twin_id).prev = twin_prev_id;
 
-        // TODO: set the face correctly
+        // Faces aren't maintained incrementally; call `rebuild_faces` to refresh
+        // them once the DCEL has reached a consistent state.
 
         full_edge_id
     }
 
+    /// Recomputes every face from the current `next` cycles of the half-edges,
+    /// walking each unvisited half-edge's loop to find its boundary.
+    ///
+    /// The face with negative signed area (or, if none is negative, the one
+    /// with the largest absolute area) is the unbounded outer face.
+    pub fn rebuild_faces(&mut self) {
+        self.faces.clear();
+
+        let mut visited = HashSet::new();
+        let half_edge_ids: Vec<HalfEdgeId> = self.half_edges.keys().collect();
+
+        for start in half_edge_ids {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut loop_edges = Vec::new();
+            let mut current = start;
+            loop {
+                loop_edges.push(current);
+                visited.insert(current);
+                current = self.half_edge(current).next;
+                if current == start {
+                    break;
+                }
+            }
+
+            let area = self.signed_area(&loop_edges);
+            let face_id = self.faces.insert_with_key(|id| Face {
+                id,
+                boundary: start,
+                area,
+                is_outer: false,
+            });
+
+            for he in loop_edges {
+                self.half_edge_mut(he).face = face_id;
+            }
+        }
+
+        let outer_id = self
+            .faces
+            .values()
+            .find(|face| face.area < 0.0)
+            .or_else(|| {
+                self.faces
+                    .values()
+                    .max_by(|a, b| a.area.abs().total_cmp(&b.area.abs()))
+            })
+            .map(|face| face.id);
+
+        if let Some(id) = outer_id {
+            self.faces.get_mut(id).unwrap().is_outer = true;
+        }
+    }
+
+    fn signed_area(&self, loop_edges: &[HalfEdgeId]) -> f32 {
+        let points: Vec<Point2> = loop_edges
+            .iter()
+            .map(|&he| self.vertex(self.half_edge(he).origin()).pos)
+            .collect();
+
+        let mut sum = 0f32;
+        for i in 0..points.len() {
+            let p = points[i];
+            let q = points[(i + 1) % points.len()];
+            sum += p.x * q.y - q.x * p.y;
+        }
+        sum / 2.0
+    }
+
+    pub fn iter_faces(&self) -> Values<'_, FaceId, Face> {
+        self.faces.values()
+    }
+
+    pub fn face(&self, face_id: FaceId) -> &Face {
+        self.faces.get(face_id).unwrap()
+    }
+
+    pub fn face_of(&self, half_edge_id: HalfEdgeId) -> FaceId {
+        self.half_edge(half_edge_id).face
+    }
+
+    /// The half-edges around `face`'s boundary, in `next` order, starting from
+    /// its stored boundary edge.
+    pub fn face_boundary(&self, face: FaceId) -> impl Iterator<Item = HalfEdgeId> + '_ {
+        let start = self.face(face).boundary;
+        let mut current = Some(start);
+
+        std::iter::from_fn(move || {
+            let this = current?;
+            let next = self.half_edge(this).next;
+            current = if next == start { None } else { Some(next) };
+            Some(this)
+        })
+    }
+
     // fn add_half_edge(&mut self, origin: VertexId, target: VertexId) -> HalfEdgeId {
     //     let edge_id = HalfEdgeId(self.half_edges.len());
 
@@ -387,3 +541,399 @@ impl<VertexData> Default for GeometricGraph<VertexData> {
         Self::new()
     }
 }
+
+const DELAUNAY_EPSILON: f32 = 1e-4;
+
+impl<VertexData> GeometricGraph<VertexData> {
+    /// Inserts `p` into a Delaunay triangulation that this graph is assumed to
+    /// already be (or, for the first three vertices, is in the process of
+    /// becoming). Locates the face containing `p`, fans that face into
+    /// triangles meeting at the new vertex, then restores the Delaunay
+    /// property with Lawson edge flips. A point outside every bounded face
+    /// is added as a vertex with no edges.
+    pub fn insert_delaunay(&mut self, p: Point2, data: VertexData) -> VertexId {
+        if self.vertices.len() < 3 {
+            let new_vertex = self.add_vertex(p, data);
+            let others: Vec<VertexId> = self
+                .vertices
+                .keys()
+                .filter(|&id| id != new_vertex)
+                .collect();
+            for other in others {
+                self.add_edge(other, new_vertex);
+            }
+            return new_vertex;
+        }
+
+        let loop_edges = self.locate_face(p);
+        let new_vertex = self.add_vertex(p, data);
+        let corners: Vec<VertexId> = loop_edges
+            .iter()
+            .map(|&he| self.half_edge(he).origin())
+            .collect();
+
+        for &corner in &corners {
+            self.add_edge(new_vertex, corner);
+        }
+
+        for he in loop_edges {
+            self.legalize_edge(he, new_vertex);
+        }
+
+        new_vertex
+    }
+
+    /// Returns the half-edges forming the boundary of the bounded face that
+    /// contains `p`, found by checking every face loop in turn. For a properly
+    /// maintained triangulation this is a triangle; if `p` lies outside every
+    /// bounded face, the result is empty.
+    fn locate_face(&self, p: Point2) -> Vec<HalfEdgeId> {
+        let mut visited = HashSet::new();
+
+        for start in self.half_edges.keys() {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut loop_edges = Vec::new();
+            let mut current = start;
+            loop {
+                loop_edges.push(current);
+                visited.insert(current);
+                current = self.half_edge(current).next;
+                if current == start {
+                    break;
+                }
+            }
+
+            if self.loop_contains(&loop_edges, p) {
+                return loop_edges;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Whether `p` is on the left of (or on) every edge of `loop_edges`, i.e.
+    /// inside the bounded face they wind counter-clockwise around. The
+    /// clockwise loop of the outer face does not match.
+    fn loop_contains(&self, loop_edges: &[HalfEdgeId], p: Point2) -> bool {
+        for &he in loop_edges {
+            let origin = self.vertex(self.half_edge(he).origin()).pos;
+            let target = self.vertex(self.half_edge(he).target()).pos;
+
+            let cross = (target.x - origin.x) * (p.y - origin.y)
+                - (target.y - origin.y) * (p.x - origin.x);
+
+            if cross < -DELAUNAY_EPSILON {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Flips `he` against its twin if doing so legalizes the triangle
+    /// `(apex, origin(he), target(he))`, then recursively legalizes the two
+    /// edges newly made adjacent to `apex`.
+    fn legalize_edge(&mut self, he: HalfEdgeId, apex: VertexId) {
+        let twin = self.half_edge(he).twin;
+        if twin == he {
+            return; // Boundary edge; nothing on the other side to test.
+        }
+
+        let twin_next = self.half_edge(twin).next;
+        if self.half_edge(twin_next).next != twin {
+            // The face on the other side isn't a triangle yet, so there's
+            // nothing to legalize against.
+            return;
+        }
+
+        let origin = self.half_edge(he).origin();
+        let target = self.half_edge(he).target();
+        let opposite = self.half_edge(twin_next).target();
+        if opposite == apex {
+            return;
+        }
+
+        let a = self.vertex(apex).pos;
+        let b = self.vertex(origin).pos;
+        let c = self.vertex(target).pos;
+        let d = self.vertex(opposite).pos;
+
+        if in_circle(a, b, c, d) {
+            let (e_bd, e_dc) = self.flip_edge(he);
+            self.legalize_edge(e_bd, apex);
+            self.legalize_edge(e_dc, apex);
+        }
+    }
+
+    /// Flips the shared edge of the two triangles on either side of `he` to
+    /// the other diagonal of their quadrilateral, reusing `he` and its twin
+    /// as the new diagonal's half-edges. Returns the two edges of the far
+    /// triangle that are now adjacent to the new diagonal, for legalization.
+    fn flip_edge(&mut self, he: HalfEdgeId) -> (HalfEdgeId, HalfEdgeId) {
+        let twin = self.half_edge(he).twin;
+
+        let b = self.half_edge(he).origin();
+        let c = self.half_edge(he).target();
+
+        let e_ca = self.half_edge(he).next;
+        let e_ab = self.half_edge(he).prev;
+        let a = self.half_edge(e_ab).origin();
+
+        let e_bd = self.half_edge(twin).next;
+        let e_dc = self.half_edge(twin).prev;
+        let d = self.half_edge(e_bd).target();
+
+        self.vertex_mut(b).edges.retain(|&e| e != he);
+        self.vertex_mut(b).incoming_edges.retain(|&e| e != twin);
+        self.vertex_mut(c).edges.retain(|&e| e != twin);
+        self.vertex_mut(c).incoming_edges.retain(|&e| e != he);
+
+        self.half_edge_mut(he).origin = a;
+        self.half_edge_mut(he).target = d;
+        self.half_edge_mut(he).next = e_dc;
+        self.half_edge_mut(he).prev = e_ca;
+
+        self.half_edge_mut(twin).origin = d;
+        self.half_edge_mut(twin).target = a;
+        self.half_edge_mut(twin).next = e_ab;
+        self.half_edge_mut(twin).prev = e_bd;
+
+        self.half_edge_mut(e_ab).next = e_bd;
+        self.half_edge_mut(e_ab).prev = twin;
+        self.half_edge_mut(e_bd).next = twin;
+        self.half_edge_mut(e_bd).prev = e_ab;
+        self.half_edge_mut(e_dc).next = e_ca;
+        self.half_edge_mut(e_dc).prev = he;
+        self.half_edge_mut(e_ca).next = he;
+        self.half_edge_mut(e_ca).prev = e_dc;
+
+        let edge_id = self.half_edge(he).edge;
+        let full_edge = self.edge_mut(edge_id);
+        if full_edge.first == he {
+            full_edge.origin = a;
+            full_edge.target = d;
+        } else {
+            full_edge.origin = d;
+            full_edge.target = a;
+        }
+
+        self.vertex_mut(a).edges.push(he);
+        self.vertex_mut(d).incoming_edges.push(he);
+        self.vertex_mut(d).edges.push(twin);
+        self.vertex_mut(a).incoming_edges.push(twin);
+
+        self.resort_vertex_edges(a);
+        self.resort_vertex_edges(b);
+        self.resort_vertex_edges(c);
+        self.resort_vertex_edges(d);
+
+        (e_bd, e_dc)
+    }
+
+    /// Re-sorts a vertex's `edges`/`incoming_edges` by angle, matching the
+    /// order `add_edge` maintains incrementally.
+    fn resort_vertex_edges(&mut self, id: VertexId) {
+        let pos = self.vertex(id).pos;
+
+        let mut edges: Vec<(f32, HalfEdgeId)> = self
+            .vertex(id)
+            .edges
+            .iter()
+            .map(|&e| {
+                let dir = self.vertex(self.half_edge(e).target()).pos - pos;
+                (dir.angle(), e)
+            })
+            .collect();
+        edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.vertex_mut(id).edges = edges.into_iter().map(|(_, e)| e).collect();
+
+        let mut incoming: Vec<(f32, HalfEdgeId)> = self
+            .vertex(id)
+            .incoming_edges
+            .iter()
+            .map(|&e| {
+                let dir = self.vertex(self.half_edge(e).origin()).pos - pos;
+                (dir.angle(), e)
+            })
+            .collect();
+        incoming.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.vertex_mut(id).incoming_edges = incoming.into_iter().map(|(_, e)| e).collect();
+    }
+}
+
+/// Whether `d` lies strictly inside the circumcircle of `a`, `b`, `c` (assumed
+/// counter-clockwise): the standard in-circle determinant test.
+fn in_circle(a: Point2, b: Point2, c: Point2, d: Point2) -> bool {
+    let ax = a.x - d.x;
+    let ay = a.y - d.y;
+    let bx = b.x - d.x;
+    let by = b.y - d.y;
+    let cx = c.x - d.x;
+    let cy = c.y - d.y;
+
+    let det = (ax * ax + ay * ay) * (bx * cy - cx * by)
+        - (bx * bx + by * by) * (ax * cy - cx * ay)
+        + (cx * cx + cy * cy) * (ax * by - bx * ay);
+
+    det > DELAUNAY_EPSILON
+}
+
+impl<VertexData> GeometricGraph<VertexData> {
+    /// Writes this graph as a Wavefront OBJ mesh: one `v x y 0` line per
+    /// vertex (in `SlotMap` iteration order), then one `f` line per bounded
+    /// face. Call [`Self::rebuild_faces`] first if the DCEL has changed since
+    /// the last rebuild, since this only reads the faces already recorded.
+    pub fn to_obj<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut obj_index = HashMap::with_capacity(self.vertices.len());
+        for (i, (id, vertex)) in self.vertices.iter().enumerate() {
+            writeln!(writer, "v {} {} 0", vertex.pos.x, vertex.pos.y)?;
+            obj_index.insert(id, i + 1); // OBJ vertex indices are 1-based.
+        }
+
+        for face in self.faces.values() {
+            if face.is_outer {
+                continue;
+            }
+
+            write!(writer, "f")?;
+            for he in self.face_boundary(face.id) {
+                write!(writer, " {}", obj_index[&self.half_edge(he).origin()])?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GeometricGraph<()> {
+    /// Reads a Wavefront OBJ mesh back into a graph: `v` lines become
+    /// vertices and `f` polygon loops become edges, deduplicating shared
+    /// edges so each geometric edge yields exactly one [`Edge`] with two
+    /// half-edge twins. An `f` line with only two vertices (an open boundary
+    /// path rather than a closed loop) contributes a single edge instead of
+    /// wrapping around. Face vertices are looked up purely by index, so they
+    /// may be listed in any order as long as the `v` lines that define them
+    /// come first, per the OBJ convention.
+    pub fn from_obj<R: BufRead>(reader: R) -> io::Result<GeometricGraph<()>> {
+        let mut graph = GeometricGraph::new();
+        let mut vertices: Vec<VertexId> = Vec::new();
+        let mut seen_edges: HashSet<(VertexId, VertexId)> = HashSet::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let x: f32 = tokens.next().and_then(|t| t.parse().ok()).unwrap_or(0.0);
+                    let y: f32 = tokens.next().and_then(|t| t.parse().ok()).unwrap_or(0.0);
+                    vertices.push(graph.add_vertex(Point2::new(x, y), ()));
+                }
+                Some("f") => {
+                    let corners: Vec<VertexId> = tokens
+                        .filter_map(|token| token.split('/').next())
+                        .filter_map(|index| index.parse::<usize>().ok())
+                        .filter_map(|index| index.checked_sub(1))
+                        .filter_map(|index| vertices.get(index).copied())
+                        .collect();
+
+                    if corners.len() < 2 {
+                        continue;
+                    }
+
+                    let is_closed_loop = corners.len() > 2;
+                    let edge_count = if is_closed_loop {
+                        corners.len()
+                    } else {
+                        corners.len() - 1
+                    };
+
+                    for i in 0..edge_count {
+                        let a = corners[i];
+                        let b = corners[(i + 1) % corners.len()];
+
+                        if !seen_edges.contains(&(a, b)) && !seen_edges.contains(&(b, a)) {
+                            graph.add_edge(a, b);
+                            seen_edges.insert((a, b));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+impl<VertexData> GeometricGraph<VertexData> {
+    /// Removes `edge` and splices the `next`/`prev` rings of its neighbors
+    /// back together, merging the face loops the edge used to separate (or,
+    /// if it was a dangling edge bordering the same loop on both sides,
+    /// shrinking that loop). Leaves either endpoint vertex in place even if
+    /// it ends up with no remaining edges; use [`Self::remove_vertex`] to
+    /// remove a vertex along with its incident edges.
+    ///
+    /// Faces aren't rebuilt as part of this; call [`Self::rebuild_faces`]
+    /// afterwards if face info is needed.
+    pub fn remove_edge(&mut self, edge_id: EdgeId) -> Result<(), DcelError> {
+        let edge = *self
+            .edges
+            .get(edge_id)
+            .ok_or(DcelError::UnknownEdge(edge_id))?;
+
+        let he = edge.first;
+        let twin = edge.second;
+
+        let he_prev = self.half_edge(he).prev;
+        let he_next = self.half_edge(he).next;
+        let twin_prev = self.half_edge(twin).prev;
+        let twin_next = self.half_edge(twin).next;
+
+        self.half_edge_mut(he_prev).next = twin_next;
+        self.half_edge_mut(twin_next).prev = he_prev;
+        self.half_edge_mut(twin_prev).next = he_next;
+        self.half_edge_mut(he_next).prev = twin_prev;
+
+        self.vertex_mut(edge.origin).edges.retain(|&e| e != he);
+        self.vertex_mut(edge.target)
+            .incoming_edges
+            .retain(|&e| e != he);
+        self.vertex_mut(edge.target).edges.retain(|&e| e != twin);
+        self.vertex_mut(edge.origin)
+            .incoming_edges
+            .retain(|&e| e != twin);
+
+        self.half_edges.remove(he);
+        self.half_edges.remove(twin);
+        self.edges.remove(edge_id);
+
+        Ok(())
+    }
+
+    /// Removes `vertex` along with every edge touching it, then frees the
+    /// vertex itself. A vertex with no incident edges is simply freed.
+    pub fn remove_vertex(&mut self, vertex_id: VertexId) -> Result<(), DcelError> {
+        if !self.vertices.contains_key(vertex_id) {
+            return Err(DcelError::UnknownVertex(vertex_id));
+        }
+
+        let incident_edges: Vec<EdgeId> = self
+            .edges
+            .values()
+            .filter(|edge| edge.origin == vertex_id || edge.target == vertex_id)
+            .map(|edge| edge.id)
+            .collect();
+
+        for edge_id in incident_edges {
+            self.remove_edge(edge_id)?;
+        }
+
+        self.vertices.remove(vertex_id);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 pub mod dcel;
 pub mod lines;
 mod randwalk;
+pub mod visit;
+pub mod voronoi;
 
+use dcel::FaceId;
 use dcel::GeometricGraph;
 use dcel::HalfEdgeId;
 use nannou::{
@@ -9,6 +12,7 @@ use nannou::{
     rand::{thread_rng, Rng},
 };
 use randwalk::MultiOscillator;
+use voronoi::CellData;
 
 const DEBUG_HALF_EDGE_OFFSET: f32 = 3.0f32;
 const DEBUG_EDGE_LENGTH: f32 = 0.9f32;
@@ -18,6 +22,11 @@ struct Model {
     edge: HalfEdgeId,
     i: usize,       // TODO: remove
     was_twin: bool, // TODO: remove
+    /// `Some` while the `L` key toggle is animating a Voronoi diagram of the
+    /// current sites towards a centroidal Voronoi tessellation via Lloyd's algorithm.
+    relaxing: Option<GeometricGraph<CellData>>,
+    /// The half-edges of the `P` key's most recent `shortest_path` query, highlighted in `debug_draw`.
+    route: Vec<HalfEdgeId>,
 }
 
 struct VertexData {
@@ -34,6 +43,17 @@ impl VertexData {
     }
 }
 
+/// A stable pseudo-random hash of a face id, used to give each cell a
+/// distinct fill color without needing any extra per-face state.
+fn face_color_bits(face: FaceId) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    face.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn create_default_example_graph() -> GeometricGraph<VertexData> {
     let mut this = GeometricGraph::new();
 
@@ -71,6 +91,8 @@ impl Model {
         for vertex in self.graph.iter_mut_vertices() {
             vertex.pos = vertex.data.original_position; //+ vertex.data.noise.generate(time);
         }
+
+        self.graph.rebuild_faces();
     }
 
     fn draw_to(&self, draw: &Draw) {
@@ -80,10 +102,28 @@ impl Model {
             .for_each(|(origin, target)| {
                 draw.line().start(origin).end(target).finish();
             });
-        // draw.polygon()
-        //     .color(rgba(5u8, 250u8, 25u8, 76u8))
-        //     .points(self.graph.iter_vertices().map(Vertex::final_pos))
-        //     .finish();
+
+        for face in self.graph.iter_faces() {
+            if face.is_outer() {
+                continue;
+            }
+
+            let points: Vec<Point2> = self
+                .graph
+                .face_boundary(face.id())
+                .map(|he| self.graph.vertex(self.graph.half_edge(he).origin()).pos)
+                .collect();
+
+            let bits = face_color_bits(face.id());
+            let color = rgba(
+                (bits & 0xFF) as u8,
+                ((bits >> 8) & 0xFF) as u8,
+                ((bits >> 16) & 0xFF) as u8,
+                120u8,
+            );
+
+            draw.polygon().color(color).points(points).finish();
+        }
 
         self.graph
             .iter_vertices()
@@ -109,18 +149,41 @@ fn main() {
 }
 
 fn event(app: &App, model: &mut Model, event: Event) {
-    if let Event::WindowEvent {
+    let Event::WindowEvent {
         id: _,
-        simple: Some(MousePressed(MouseButton::Left)),
+        simple: Some(simple_event),
     } = event
-    {
-        let pos = app.mouse.position();
-        let other = model.graph.iter_vertices().last().unwrap().id(); // TODO: temp
-        let vertex = model
-            .graph
-            .add_vertex(pos, VertexData::rand_new(pos, &mut thread_rng()));
-
-        model.graph.add_edge(other, vertex); // TODO: temp
+    else {
+        return;
+    };
+
+    match simple_event {
+        MousePressed(MouseButton::Left) => {
+            let pos = app.mouse.position();
+            model
+                .graph
+                .insert_delaunay(pos, VertexData::rand_new(pos, &mut thread_rng()));
+        }
+        KeyPressed(Key::L) => {
+            model.relaxing = match model.relaxing.take() {
+                Some(_) => None,
+                None => {
+                    let sites: Vec<Point2> =
+                        model.graph.iter_vertices().map(|vertex| vertex.pos).collect();
+                    Some(voronoi::voronoi(&sites))
+                }
+            };
+        }
+        KeyPressed(Key::P) => {
+            let mut vertices = model.graph.iter_vertices().map(|vertex| vertex.id());
+            model.route = match (vertices.next(), vertices.last()) {
+                (Some(start), Some(goal)) => {
+                    model.graph.shortest_path(start, goal).unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+        }
+        _ => {}
     }
 }
 
@@ -132,12 +195,18 @@ fn model(_: &App) -> Model {
         i: 0,
         edge,
         was_twin: false,
+        relaxing: None,
+        route: Vec::new(),
     }
 }
 
 fn update(app: &App, model: &mut Model, update: Update) {
     model.update(app, update);
 
+    if let Some(diagram) = &mut model.relaxing {
+        diagram.lloyd_step();
+    }
+
     model.i += 1;
     if model.i % 100 == 0 && model.graph.iter_edges().count() > 0 {
         if model.was_twin || model.i % 7 != 0 {
@@ -150,9 +219,9 @@ fn update(app: &App, model: &mut Model, update: Update) {
     }
 
     if model.i % 1234 == 0 {
-        model
-            .graph
-            .remove_edge(model.graph.iter_edges().next().unwrap().id());
+        if let Some(edge_id) = model.graph.iter_edges().next().map(|edge| edge.id()) {
+            let _ = model.graph.remove_edge(edge_id);
+        }
     }
 }
 
@@ -161,12 +230,34 @@ fn view(app: &App, model: &Model, frame: Frame) {
 
     draw.background().color(rgb(100u8, 100u8, 100u8));
 
-    // model.draw_to(&draw);
-    debug_draw(&draw, model); // TODO: remove debug
+    if let Some(diagram) = &model.relaxing {
+        draw_voronoi(&draw, diagram);
+    } else {
+        // model.draw_to(&draw);
+        debug_draw(&draw, model); // TODO: remove debug
+    }
 
     draw.to_frame(app, &frame).unwrap();
 }
 
+fn draw_voronoi(draw: &Draw, graph: &GeometricGraph<CellData>) {
+    graph
+        .iter_edges()
+        .map(|edge| (graph.origin(edge).pos, graph.target(edge).pos))
+        .for_each(|(origin, target)| {
+            draw.line().start(origin).end(target).finish();
+        });
+
+    graph.iter_vertices().for_each(|vertex| {
+        draw.ellipse()
+            .color(BLACK)
+            .xy(vertex.pos)
+            .w(6f32)
+            .h(6f32)
+            .finish();
+    });
+}
+
 fn debug_draw(draw: &Draw, model: &Model) {
     let graph = &model.graph;
 
@@ -197,6 +288,8 @@ fn debug_draw(draw: &Draw, model: &Model) {
 
             if model.edge == edge.id() {
                 arrow = arrow.color(RED);
+            } else if model.route.contains(&edge.id()) {
+                arrow = arrow.color(GREEN);
             }
 
             arrow.finish();
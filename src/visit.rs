@@ -0,0 +1,201 @@
+//! Adapts [`GeometricGraph`] to petgraph's `visit` traits, so algorithms like
+//! Dijkstra, A*, connected-components, and minimum spanning tree can run
+//! directly over the DCEL without copying it into a separate graph.
+//!
+//! Edges are treated as undirected (an `Edge` connects its two endpoints
+//! regardless of which one happened to be `origin` when it was added) and
+//! weighted by the Euclidean distance between their endpoints.
+//!
+//! `GraphBase`/`Data`/`NodeIndexable`/`Visitable` are implemented on the
+//! owned `GeometricGraph<V>`, matching petgraph's own `Graph<N, E, Ty, Ix>`
+//! convention; petgraph's blanket impls then cover `&GeometricGraph<V>` for
+//! free. `IntoNeighbors`/`IntoEdgeReferences`/`IntoEdges` take `self` by
+//! value and so are implemented directly on the reference type.
+
+use std::collections::HashSet;
+
+use petgraph::visit::{
+    Data, EdgeRef, GraphBase, IntoEdgeReferences, IntoEdges, IntoNeighbors, NodeIndexable,
+    Visitable,
+};
+
+use crate::dcel::{EdgeId, GeometricGraph, HalfEdgeId, VertexId};
+
+impl<V> GraphBase for GeometricGraph<V> {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+}
+
+impl<V> Data for GeometricGraph<V> {
+    type NodeWeight = V;
+    type EdgeWeight = f32;
+}
+
+impl<V> NodeIndexable for GeometricGraph<V> {
+    fn node_bound(&self) -> usize {
+        self.iter_vertices().count()
+    }
+
+    fn to_index(&self, a: VertexId) -> usize {
+        self.iter_vertices()
+            .position(|vertex| vertex.id() == a)
+            .expect("vertex does not belong to this graph")
+    }
+
+    fn from_index(&self, i: usize) -> VertexId {
+        self.iter_vertices()
+            .nth(i)
+            .expect("index out of bounds")
+            .id()
+    }
+}
+
+impl<V> Visitable for GeometricGraph<V> {
+    type Map = HashSet<VertexId>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::new()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<'a, V> IntoNeighbors for &'a GeometricGraph<V> {
+    type Neighbors = std::vec::IntoIter<VertexId>;
+
+    fn neighbors(self, n: VertexId) -> Self::Neighbors {
+        let neighbors: Vec<VertexId> = self
+            .iter_edges()
+            .filter_map(|edge| {
+                if edge.origin() == n {
+                    Some(edge.target())
+                } else if edge.target() == n {
+                    Some(edge.origin())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        neighbors.into_iter()
+    }
+}
+
+/// An [`EdgeRef`] over a [`GeometricGraph`] edge, weighted by Euclidean distance.
+#[derive(Clone, Copy)]
+pub struct DistanceEdgeRef {
+    id: EdgeId,
+    source: VertexId,
+    target: VertexId,
+    weight: f32,
+}
+
+impl EdgeRef for DistanceEdgeRef {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+    type Weight = f32;
+
+    fn source(&self) -> VertexId {
+        self.source
+    }
+
+    fn target(&self) -> VertexId {
+        self.target
+    }
+
+    fn weight(&self) -> &f32 {
+        &self.weight
+    }
+
+    fn id(&self) -> EdgeId {
+        self.id
+    }
+}
+
+impl<'a, V> IntoEdgeReferences for &'a GeometricGraph<V> {
+    type EdgeRef = DistanceEdgeRef;
+    type EdgeReferences = std::vec::IntoIter<DistanceEdgeRef>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        let refs: Vec<DistanceEdgeRef> = self
+            .iter_edges()
+            .map(|edge| {
+                let origin = self.vertex(edge.origin()).pos;
+                let target = self.vertex(edge.target()).pos;
+
+                DistanceEdgeRef {
+                    id: edge.id(),
+                    source: edge.origin(),
+                    target: edge.target(),
+                    weight: (target - origin).length(),
+                }
+            })
+            .collect();
+
+        refs.into_iter()
+    }
+}
+
+impl<'a, V> IntoEdges for &'a GeometricGraph<V> {
+    type Edges = std::vec::IntoIter<DistanceEdgeRef>;
+
+    fn edges(self, a: VertexId) -> Self::Edges {
+        let refs: Vec<DistanceEdgeRef> = self
+            .iter_edges()
+            .filter_map(|edge| {
+                let (source, target) = if edge.origin() == a {
+                    (edge.origin(), edge.target())
+                } else if edge.target() == a {
+                    (edge.target(), edge.origin())
+                } else {
+                    return None;
+                };
+
+                let weight = (self.vertex(target).pos - self.vertex(source).pos).length();
+                Some(DistanceEdgeRef {
+                    id: edge.id(),
+                    source,
+                    target,
+                    weight,
+                })
+            })
+            .collect();
+
+        refs.into_iter()
+    }
+}
+
+impl<V> GeometricGraph<V> {
+    /// The shortest path from `start` to `goal` by total Euclidean edge
+    /// length, found via `petgraph::algo::astar` running directly over this
+    /// graph's `IntoEdges`/`Visitable` impls (with a zero heuristic, so it's
+    /// exactly Dijkstra). Returned as the half-edges to traverse in order, so
+    /// the visualization can highlight a route the same way `model.edge` is
+    /// highlighted in `debug_draw`. Returns `None` if `goal` isn't reachable.
+    pub fn shortest_path(&self, start: VertexId, goal: VertexId) -> Option<Vec<HalfEdgeId>> {
+        let (_cost, vertices) =
+            petgraph::algo::astar(self, start, |n| n == goal, |edge| *edge.weight(), |_| 0.0)?;
+
+        let path = vertices
+            .windows(2)
+            .map(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                self.iter_edges()
+                    .find_map(|edge| {
+                        if edge.origin() == a && edge.target() == b {
+                            Some(edge.half_edge())
+                        } else if edge.origin() == b && edge.target() == a {
+                            Some(edge.twin_half_edge())
+                        } else {
+                            None
+                        }
+                    })
+                    .expect("astar only steps along edges that exist in this graph")
+            })
+            .collect();
+
+        Some(path)
+    }
+}
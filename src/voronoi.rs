@@ -0,0 +1,738 @@
+//! Computing Voronoi diagrams via Fortune's sweepline algorithm.
+//!
+//! The sweep moves top-to-bottom (decreasing `y`). A beach line of parabolic
+//! arcs, one per site currently "seen" by the sweep, is kept ordered
+//! left-to-right by their current breakpoints. Site events insert a new arc
+//! into the beach line; circle events remove an arc once it has shrunk to
+//! nothing and fix a Voronoi vertex at the point where its neighbors meet.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use nannou::prelude::{Point2, Vec2};
+
+use crate::dcel::{GeometricGraph, VertexId};
+
+/// Per-vertex payload attached to the graph produced by [`voronoi`].
+///
+/// A Voronoi vertex is generally shared by three cells, so there is no single
+/// "owning" site. Each vertex is tagged with one of its incident sites (the
+/// arc that collapsed to create it, or the left cell of the edge that was
+/// clipped to produce it) so simple per-cell consumers have something to key
+/// off of.
+pub struct CellData {
+    pub site: Point2,
+}
+
+const EPSILON: f32 = 1e-4;
+
+/// Computes the Voronoi diagram of `sites` and emits it as a [`GeometricGraph`].
+///
+/// Dangling edges (cells that extend to infinity) are clipped against a
+/// bounding box computed from the sites' extent plus a margin, and the
+/// clipped points are then connected to each other around the box's
+/// perimeter, so every cell — including the unbounded ones — closes into a
+/// simple polygon face rather than leaving pendant half-edges.
+pub fn voronoi(sites: &[Point2]) -> GeometricGraph<CellData> {
+    let mut graph = GeometricGraph::new();
+
+    if sites.len() < 2 {
+        for &site in sites {
+            graph.add_vertex(site, CellData { site });
+        }
+        return graph;
+    }
+
+    let bbox = bounding_box(sites);
+
+    let mut beachline: Vec<BeachArc> = Vec::new();
+    let mut edges: Vec<PartialEdge> = Vec::new();
+    let mut events: BinaryHeap<Event> = BinaryHeap::new();
+    let mut next_arc_uid: u64 = 0;
+    let mut next_event_id: u64 = 0;
+
+    for (index, &site) in sites.iter().enumerate() {
+        events.push(Event::Site {
+            y: site.y,
+            x: site.x,
+            index,
+        });
+    }
+
+    while let Some(event) = events.pop() {
+        match event {
+            Event::Site { index, .. } => handle_site_event(
+                sites,
+                index,
+                &mut beachline,
+                &mut edges,
+                &mut events,
+                &mut next_arc_uid,
+                &mut next_event_id,
+            ),
+            Event::Circle(circle) => handle_circle_event(
+                sites,
+                circle,
+                &mut beachline,
+                &mut edges,
+                &mut events,
+                &mut graph,
+                &mut next_event_id,
+            ),
+        }
+    }
+
+    finish_edges(&mut graph, &edges, sites, &bbox);
+
+    graph
+}
+
+#[derive(Clone, Copy)]
+struct Bbox {
+    min: Point2,
+    max: Point2,
+}
+
+fn bounding_box(sites: &[Point2]) -> Bbox {
+    let mut min = sites[0];
+    let mut max = sites[0];
+    for &site in sites {
+        min.x = min.x.min(site.x);
+        min.y = min.y.min(site.y);
+        max.x = max.x.max(site.x);
+        max.y = max.y.max(site.y);
+    }
+
+    let margin = ((max.x - min.x).max(max.y - min.y) * 0.5).max(10.0);
+    min -= Vec2::splat(margin);
+    max += Vec2::splat(margin);
+
+    Bbox { min, max }
+}
+
+#[derive(Clone, Copy)]
+struct BeachArc {
+    uid: u64,
+    site: usize,
+    left_edge: Option<usize>,
+    right_edge: Option<usize>,
+    /// Id of the most recently scheduled circle event for this arc; a popped
+    /// [`CircleEvent`] whose id doesn't match this is stale.
+    circle_event: Option<u64>,
+}
+
+/// A Voronoi edge in progress. Its line is the perpendicular bisector of
+/// `left_site`/`right_site`; `start_point` is where it was born (either a real
+/// Voronoi vertex from a circle event, in which case `start_vertex` is also
+/// set, or the point where the breakpoint first appeared). `end_vertex` is
+/// filled in once a later circle event closes the other side.
+struct PartialEdge {
+    left_site: usize,
+    right_site: usize,
+    start_point: Point2,
+    start_vertex: Option<VertexId>,
+    end_vertex: Option<VertexId>,
+    direction: Vec2,
+}
+
+#[derive(Clone, Copy)]
+struct CircleEvent {
+    y: f32,
+    center: Point2,
+    arc_uid: u64,
+    event_id: u64,
+}
+
+enum Event {
+    Site { y: f32, x: f32, index: usize },
+    Circle(CircleEvent),
+}
+
+impl Event {
+    fn y(&self) -> f32 {
+        match self {
+            Event::Site { y, .. } => *y,
+            Event::Circle(c) => c.y,
+        }
+    }
+
+    fn x(&self) -> f32 {
+        match self {
+            Event::Site { x, .. } => *x,
+            Event::Circle(c) => c.center.x,
+        }
+    }
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.y() == other.y() && self.x() == other.x()
+    }
+}
+
+impl Eq for Event {}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // A max-heap on `y` visits the topmost (largest `y`) event first, which is
+        // exactly the order the sweep line wants. Ties are broken so the smaller
+        // `x` counts as "greater", processing equal-`y` sites left-to-right.
+        self.y()
+            .total_cmp(&other.y())
+            .then_with(|| other.x().total_cmp(&self.x()))
+    }
+}
+
+/// `y` coordinate of the parabola focused at `site` for the sweep line at `directrix`.
+fn parabola_y(site: Point2, x: f32, directrix: f32) -> f32 {
+    if (site.y - directrix).abs() < EPSILON {
+        return site.y;
+    }
+
+    let dp = 2.0 * (site.y - directrix);
+    (x * x - 2.0 * site.x * x + site.x * site.x + site.y * site.y - directrix * directrix) / dp
+}
+
+/// `x` coordinate of the breakpoint between the parabolas focused at `left` and `right`.
+fn breakpoint_x(left: Point2, right: Point2, directrix: f32) -> f32 {
+    if (left.y - right.y).abs() < EPSILON {
+        return (left.x + right.x) / 2.0;
+    }
+    if (left.y - directrix).abs() < EPSILON {
+        return left.x;
+    }
+    if (right.y - directrix).abs() < EPSILON {
+        return right.x;
+    }
+
+    let dl = 2.0 * (left.y - directrix);
+    let dr = 2.0 * (right.y - directrix);
+
+    let a = 1.0 / dl - 1.0 / dr;
+    let b = -2.0 * (left.x / dl - right.x / dr);
+    let c = (left.x * left.x + left.y * left.y - directrix * directrix) / dl
+        - (right.x * right.x + right.y * right.y - directrix * directrix) / dr;
+
+    if a.abs() < EPSILON {
+        return -c / b;
+    }
+
+    let discriminant = (b * b - 4.0 * a * c).max(0.0).sqrt();
+    let x1 = (-b + discriminant) / (2.0 * a);
+    let x2 = (-b - discriminant) / (2.0 * a);
+
+    if left.y < right.y {
+        x1.max(x2)
+    } else {
+        x1.min(x2)
+    }
+}
+
+/// The index of the arc directly above `x` on the beach line.
+fn arc_at(beachline: &[BeachArc], sites: &[Point2], x: f32, directrix: f32) -> usize {
+    for i in 0..beachline.len().saturating_sub(1) {
+        let bp = breakpoint_x(
+            sites[beachline[i].site],
+            sites[beachline[i + 1].site],
+            directrix,
+        );
+        if x < bp {
+            return i;
+        }
+    }
+    beachline.len() - 1
+}
+
+/// The center and radius of the circle through `a`, `b`, `c`, or `None` if the
+/// three sites are collinear or curve the wrong way (diverging, not converging).
+fn circumcircle(a: Point2, b: Point2, c: Point2) -> Option<(Point2, f32)> {
+    let ax = b.x - a.x;
+    let ay = b.y - a.y;
+    let bx = c.x - a.x;
+    let by = c.y - a.y;
+
+    let d = 2.0 * (ax * by - ay * bx);
+    if d >= -EPSILON {
+        // Collinear, or a,b,c turn counter-clockwise (the arcs are diverging).
+        return None;
+    }
+
+    let a_sq = ax * ax + ay * ay;
+    let b_sq = bx * bx + by * by;
+
+    let ux = (by * a_sq - ay * b_sq) / d;
+    let uy = (ax * b_sq - bx * a_sq) / d;
+
+    let center = Point2::new(a.x + ux, a.y + uy);
+    let radius = (ux * ux + uy * uy).sqrt();
+    Some((center, radius))
+}
+
+fn bisector_direction(left: Point2, right: Point2) -> Vec2 {
+    let d = right - left;
+    Vec2::new(-d.y, d.x).normalize()
+}
+
+fn new_arc(site: usize, next_arc_uid: &mut u64) -> BeachArc {
+    BeachArc {
+        uid: next_uid(next_arc_uid),
+        site,
+        left_edge: None,
+        right_edge: None,
+        circle_event: None,
+    }
+}
+
+fn next_uid(counter: &mut u64) -> u64 {
+    let uid = *counter;
+    *counter += 1;
+    uid
+}
+
+fn handle_site_event(
+    sites: &[Point2],
+    index: usize,
+    beachline: &mut Vec<BeachArc>,
+    edges: &mut Vec<PartialEdge>,
+    events: &mut BinaryHeap<Event>,
+    next_arc_uid: &mut u64,
+    next_event_id: &mut u64,
+) {
+    let site = sites[index];
+
+    if beachline.is_empty() {
+        beachline.push(new_arc(index, next_arc_uid));
+        return;
+    }
+
+    let pos = arc_at(beachline, sites, site.x, site.y);
+    let old = beachline[pos];
+    let old_site = sites[old.site];
+
+    // Coincident sites: the new site has nowhere new to go, so it doesn't
+    // contribute a distinguishable cell.
+    if (old_site - site).length() < EPSILON {
+        return;
+    }
+
+    let tangent = Point2::new(site.x, parabola_y(old_site, site.x, site.y));
+
+    let left_edge_idx = edges.len();
+    edges.push(PartialEdge {
+        left_site: old.site,
+        right_site: index,
+        start_point: tangent,
+        start_vertex: None,
+        end_vertex: None,
+        direction: bisector_direction(old_site, site),
+    });
+
+    let right_edge_idx = edges.len();
+    edges.push(PartialEdge {
+        left_site: index,
+        right_site: old.site,
+        start_point: tangent,
+        start_vertex: None,
+        end_vertex: None,
+        direction: bisector_direction(site, old_site),
+    });
+
+    let left_copy = BeachArc {
+        uid: next_uid(next_arc_uid),
+        site: old.site,
+        left_edge: old.left_edge,
+        right_edge: Some(left_edge_idx),
+        circle_event: None,
+    };
+    let new_arc_entry = BeachArc {
+        uid: next_uid(next_arc_uid),
+        site: index,
+        left_edge: Some(left_edge_idx),
+        right_edge: Some(right_edge_idx),
+        circle_event: None,
+    };
+    let right_copy = BeachArc {
+        uid: next_uid(next_arc_uid),
+        site: old.site,
+        left_edge: Some(right_edge_idx),
+        right_edge: old.right_edge,
+        circle_event: None,
+    };
+
+    beachline.splice(pos..=pos, [left_copy, new_arc_entry, right_copy]);
+
+    check_circle_event(sites, beachline, pos, site.y, events, next_event_id);
+    check_circle_event(sites, beachline, pos + 2, site.y, events, next_event_id);
+}
+
+/// Schedules a circle event for the arc at `pos`, if its two neighbors exist
+/// and the three sites actually converge below the current sweep line.
+fn check_circle_event(
+    sites: &[Point2],
+    beachline: &mut [BeachArc],
+    pos: usize,
+    sweep_y: f32,
+    events: &mut BinaryHeap<Event>,
+    next_event_id: &mut u64,
+) {
+    if pos == 0 || pos + 1 >= beachline.len() {
+        return;
+    }
+    beachline[pos].circle_event = None;
+
+    let left = sites[beachline[pos - 1].site];
+    let mid = sites[beachline[pos].site];
+    let right = sites[beachline[pos + 1].site];
+
+    let Some((center, radius)) = circumcircle(left, mid, right) else {
+        return;
+    };
+
+    let event_y = center.y - radius;
+    if event_y > sweep_y + EPSILON {
+        // The event already happened above the current sweep position.
+        return;
+    }
+
+    let event_id = next_uid(next_event_id);
+    beachline[pos].circle_event = Some(event_id);
+    events.push(Event::Circle(CircleEvent {
+        y: event_y,
+        center,
+        arc_uid: beachline[pos].uid,
+        event_id,
+    }));
+}
+
+fn handle_circle_event(
+    sites: &[Point2],
+    circle: CircleEvent,
+    beachline: &mut Vec<BeachArc>,
+    edges: &mut Vec<PartialEdge>,
+    events: &mut BinaryHeap<Event>,
+    graph: &mut GeometricGraph<CellData>,
+    next_event_id: &mut u64,
+) {
+    let Some(pos) = beachline.iter().position(|arc| arc.uid == circle.arc_uid) else {
+        return; // Stale: the arc was already removed.
+    };
+    if beachline[pos].circle_event != Some(circle.event_id) {
+        return; // Stale: a newer event superseded this one.
+    }
+    if pos == 0 || pos + 1 >= beachline.len() {
+        return;
+    }
+
+    let arc = beachline[pos];
+    let vertex = graph.add_vertex(
+        circle.center,
+        CellData {
+            site: sites[arc.site],
+        },
+    );
+
+    if let Some(idx) = arc.left_edge {
+        edges[idx].end_vertex = Some(vertex);
+    }
+    if let Some(idx) = arc.right_edge {
+        edges[idx].end_vertex = Some(vertex);
+    }
+
+    beachline.remove(pos);
+
+    let left_site = beachline[pos - 1].site;
+    let right_site = beachline[pos].site;
+
+    let new_edge_idx = edges.len();
+    edges.push(PartialEdge {
+        left_site,
+        right_site,
+        start_point: circle.center,
+        start_vertex: Some(vertex),
+        end_vertex: None,
+        direction: bisector_direction(sites[left_site], sites[right_site]),
+    });
+
+    beachline[pos - 1].right_edge = Some(new_edge_idx);
+    beachline[pos].left_edge = Some(new_edge_idx);
+
+    if pos > 0 {
+        check_circle_event(sites, beachline, pos - 1, circle.y, events, next_event_id);
+    }
+    check_circle_event(sites, beachline, pos, circle.y, events, next_event_id);
+}
+
+fn finish_edges(
+    graph: &mut GeometricGraph<CellData>,
+    edges: &[PartialEdge],
+    sites: &[Point2],
+    bbox: &Bbox,
+) {
+    // Every vertex created here to clip a dangling ray sits on the bbox
+    // perimeter; collected alongside where it falls on that perimeter so
+    // `close_bbox_perimeter` can wire them (and the box's corners) into a
+    // closed loop, giving every unbounded cell an actual boundary edge along
+    // the box instead of leaving a dangling pendant half-edge.
+    let mut boundary_vertices: Vec<(f32, VertexId)> = Vec::new();
+
+    for edge in edges {
+        let start = match edge.start_vertex {
+            Some(v) => v,
+            None => {
+                let p = ray_bbox_intersection(edge.start_point, -edge.direction, bbox);
+                let v = graph.add_vertex(
+                    p,
+                    CellData {
+                        site: sites[edge.left_site],
+                    },
+                );
+                boundary_vertices.push((perimeter_param(p, bbox), v));
+                v
+            }
+        };
+
+        let end = match edge.end_vertex {
+            Some(v) => v,
+            None => {
+                let p = ray_bbox_intersection(edge.start_point, edge.direction, bbox);
+                let v = graph.add_vertex(
+                    p,
+                    CellData {
+                        site: sites[edge.right_site],
+                    },
+                );
+                boundary_vertices.push((perimeter_param(p, bbox), v));
+                v
+            }
+        };
+
+        if start != end {
+            graph.add_edge(start, end);
+        }
+    }
+
+    close_bbox_perimeter(graph, boundary_vertices, sites, bbox);
+}
+
+/// Connects every bbox-clipped vertex (plus the box's four corners, so cells
+/// that clip to two different sides still close) into a single loop around
+/// the bounding box's perimeter, walking counter-clockwise from `bbox.min`.
+fn close_bbox_perimeter(
+    graph: &mut GeometricGraph<CellData>,
+    mut boundary_vertices: Vec<(f32, VertexId)>,
+    sites: &[Point2],
+    bbox: &Bbox,
+) {
+    if boundary_vertices.is_empty() {
+        return;
+    }
+
+    let corners = [
+        Point2::new(bbox.min.x, bbox.min.y),
+        Point2::new(bbox.max.x, bbox.min.y),
+        Point2::new(bbox.max.x, bbox.max.y),
+        Point2::new(bbox.min.x, bbox.max.y),
+    ];
+    for corner in corners {
+        let nearest = nearest_site_index(sites, corner);
+        let v = graph.add_vertex(
+            corner,
+            CellData {
+                site: sites[nearest],
+            },
+        );
+        boundary_vertices.push((perimeter_param(corner, bbox), v));
+    }
+
+    boundary_vertices.sort_by(|a, b| a.0.total_cmp(&b.0));
+    boundary_vertices.dedup_by(|a, b| (a.0 - b.0).abs() < EPSILON);
+
+    let n = boundary_vertices.len();
+    for i in 0..n {
+        let (_, a) = boundary_vertices[i];
+        let (_, b) = boundary_vertices[(i + 1) % n];
+        if a != b {
+            graph.add_edge(a, b);
+        }
+    }
+}
+
+/// How far `p` (assumed to lie on `bbox`'s perimeter) is along that perimeter,
+/// walking counter-clockwise from `bbox.min` along the bottom, right, top, then left
+/// edge. Used purely to order perimeter points for [`close_bbox_perimeter`].
+fn perimeter_param(p: Point2, bbox: &Bbox) -> f32 {
+    let width = bbox.max.x - bbox.min.x;
+    let height = bbox.max.y - bbox.min.y;
+
+    if (p.y - bbox.min.y).abs() < EPSILON {
+        p.x - bbox.min.x
+    } else if (p.x - bbox.max.x).abs() < EPSILON {
+        width + (p.y - bbox.min.y)
+    } else if (p.y - bbox.max.y).abs() < EPSILON {
+        width + height + (bbox.max.x - p.x)
+    } else {
+        2.0 * width + height + (bbox.max.y - p.y)
+    }
+}
+
+fn ray_bbox_intersection(origin: Point2, direction: Vec2, bbox: &Bbox) -> Point2 {
+    if direction.length_squared() < EPSILON {
+        return origin.clamp(bbox.min, bbox.max);
+    }
+
+    let mut best_t = f32::INFINITY;
+
+    if direction.x.abs() > EPSILON {
+        for &x in &[bbox.min.x, bbox.max.x] {
+            let t = (x - origin.x) / direction.x;
+            if t > EPSILON {
+                let y = origin.y + t * direction.y;
+                if y >= bbox.min.y - EPSILON && y <= bbox.max.y + EPSILON {
+                    best_t = best_t.min(t);
+                }
+            }
+        }
+    }
+    if direction.y.abs() > EPSILON {
+        for &y in &[bbox.min.y, bbox.max.y] {
+            let t = (y - origin.y) / direction.y;
+            if t > EPSILON {
+                let x = origin.x + t * direction.x;
+                if x >= bbox.min.x - EPSILON && x <= bbox.max.x + EPSILON {
+                    best_t = best_t.min(t);
+                }
+            }
+        }
+    }
+
+    if best_t.is_finite() {
+        (origin + direction * best_t).clamp(bbox.min, bbox.max)
+    } else {
+        origin.clamp(bbox.min, bbox.max)
+    }
+}
+
+impl GeometricGraph<CellData> {
+    /// Moves every site to the centroid of its bounded Voronoi cell and
+    /// recomputes the diagram from the updated sites — one step of Lloyd's
+    /// algorithm towards a centroidal Voronoi tessellation. Sites whose cell
+    /// touches the bounding box are clamped so they don't drift off-canvas;
+    /// sites whose cell has (near-)zero area are left in place.
+    ///
+    /// Every cell — even the unbounded ones — needs an actual closed boundary
+    /// loop for this to see it in [`GeometricGraph::iter_faces`] at all; that
+    /// depends on [`voronoi`] having wired its bbox-clipped edges into a
+    /// closed perimeter loop rather than leaving them as dangling pendants.
+    pub fn lloyd_step(&mut self) {
+        self.rebuild_faces();
+
+        let sites = unique_sites(self);
+        if sites.len() < 2 {
+            return;
+        }
+        let bbox = bounding_box(&sites);
+
+        let mut relaxed = sites.clone();
+
+        for face in self.iter_faces() {
+            if face.is_outer() {
+                continue;
+            }
+
+            let corners: Vec<Point2> = self
+                .face_boundary(face.id())
+                .map(|he| self.vertex(self.half_edge(he).origin()).pos)
+                .collect();
+
+            if corners.len() < 3 {
+                continue;
+            }
+
+            let Some(centroid) = polygon_centroid(&corners) else {
+                continue;
+            };
+
+            let average =
+                corners.iter().fold(Vec2::ZERO, |acc, &p| acc + p) / corners.len() as f32;
+            let site_idx = nearest_site_index(&sites, average);
+
+            let touches_bbox = corners.iter().any(|p| {
+                (p.x - bbox.min.x).abs() < EPSILON
+                    || (p.x - bbox.max.x).abs() < EPSILON
+                    || (p.y - bbox.min.y).abs() < EPSILON
+                    || (p.y - bbox.max.y).abs() < EPSILON
+            });
+
+            relaxed[site_idx] = if touches_bbox {
+                centroid.clamp(bbox.min, bbox.max)
+            } else {
+                centroid
+            };
+        }
+
+        *self = voronoi(&relaxed);
+    }
+
+    /// Runs [`Self::lloyd_step`] `iterations` times, animating the site set
+    /// towards an evenly spaced centroidal Voronoi tessellation.
+    pub fn relax(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            self.lloyd_step();
+        }
+    }
+}
+
+/// The distinct sites tagged onto this graph's vertices, deduplicated by
+/// position. Every site contributes at least one vertex, so this recovers the
+/// original site set without the graph needing to store it separately.
+fn unique_sites(graph: &GeometricGraph<CellData>) -> Vec<Point2> {
+    let mut sites: Vec<Point2> = Vec::new();
+    for vertex in graph.iter_vertices() {
+        let site = vertex.data.site;
+        if !sites.iter().any(|&s| (s - site).length() < EPSILON) {
+            sites.push(site);
+        }
+    }
+    sites
+}
+
+fn nearest_site_index(sites: &[Point2], p: Point2) -> usize {
+    sites
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (**a - p).length_squared().total_cmp(&(**b - p).length_squared()))
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+/// The centroid of a (possibly non-convex) simple polygon, or `None` if its
+/// signed area is too close to zero to divide by.
+fn polygon_centroid(points: &[Point2]) -> Option<Point2> {
+    let n = points.len();
+    let mut area = 0f32;
+    let mut cx = 0f32;
+    let mut cy = 0f32;
+
+    for i in 0..n {
+        let p = points[i];
+        let q = points[(i + 1) % n];
+        let cross = p.x * q.y - q.x * p.y;
+        area += cross;
+        cx += (p.x + q.x) * cross;
+        cy += (p.y + q.y) * cross;
+    }
+
+    area *= 0.5;
+    if area.abs() < EPSILON {
+        return None;
+    }
+
+    let factor = 1.0 / (6.0 * area);
+    Some(Point2::new(cx * factor, cy * factor))
+}